@@ -7,8 +7,11 @@ use point_viewer::octree::OctreeFactory;
 use point_viewer_grpc::octree_from_grpc_address;
 use std::error::Error;
 use std::path::Path;
+use xray::colormap::Colormap;
+use xray::filters;
 use xray::generation::{
-    xray_from_points, ColoringStrategyArgument, ColoringStrategyKind, TileBackgroundColorArgument,
+    with_auto_range, xray_image_from_points, Axis, ColoringStrategyArgument, ColoringStrategyKind,
+    ColormapSourceArgument, TileBackgroundColorArgument,
 };
 
 fn parse_arguments() -> clap::ArgMatches<'static> {
@@ -33,29 +36,109 @@ fn parse_arguments() -> clap::ArgMatches<'static> {
                 .default_value("xray"),
             clap::Arg::with_name("min_intensity")
                 .help(
-                    "Minimum intensity of all points for color scaling. \
-                     Only used for 'colored_with_intensity'.",
+                    "Minimum intensity of all points for color scaling. Only used for \
+                     'colored_with_intensity'; required unless --auto_range is set.",
                 )
                 .long("min_intensity")
-                .takes_value(true)
-                .required_if("coloring_strategy", "colored_with_intensity"),
+                .takes_value(true),
             clap::Arg::with_name("max_intensity")
                 .help(
-                    "Minimum intensity of all points for color scaling. \
-                     Only used for 'colored_with_intensity'.",
+                    "Maximum intensity of all points for color scaling. Only used for \
+                     'colored_with_intensity'; required unless --auto_range is set.",
                 )
                 .long("max_intensity")
-                .takes_value(true)
-                .required_if("coloring_strategy", "colored_with_intensity"),
+                .takes_value(true),
             clap::Arg::with_name("max_stddev")
                 .help(
                     "Maximum stddev for colored_with_height_stddev. Every stddev above this \
-                     will be clamped to this value and appear saturated in the X-Rays. \
-                     Only used for 'colored_with_height_stddev'.",
+                     will be clamped to this value and appear saturated in the X-Rays. Only \
+                     used for 'colored_with_height_stddev'; required unless --auto_range is set.",
                 )
                 .long("max_stddev")
+                .takes_value(true),
+            clap::Arg::with_name("colormap")
+                .help("Perceptual colormap to map the scalar through.")
+                .long("colormap")
+                .takes_value(true)
+                .possible_values(&Colormap::variants())
+                .default_value("turbo"),
+            clap::Arg::with_name("colormap_min")
+                .help(
+                    "Lower bound of the colormap domain, in the units of --colormap_source for \
+                     'colored_with_colormap' or of --axis for 'colored_by_axis'. Required for \
+                     those strategies unless --auto_range is set.",
+                )
+                .long("colormap_min")
+                .takes_value(true),
+            clap::Arg::with_name("colormap_max")
+                .help(
+                    "Upper bound of the colormap domain, in the units of --colormap_source for \
+                     'colored_with_colormap' or of --axis for 'colored_by_axis'. Required for \
+                     those strategies unless --auto_range is set.",
+                )
+                .long("colormap_max")
+                .takes_value(true),
+            clap::Arg::with_name("colormap_source")
+                .help(
+                    "Per-pixel scalar to feed into the colormap: the point height, its \
+                     intensity, or the number of points that landed in the pixel. Only used \
+                     for 'colored_with_colormap'.",
+                )
+                .long("colormap_source")
+                .takes_value(true)
+                .possible_values(&ColormapSourceArgument::variants())
+                .default_value("height"),
+            clap::Arg::with_name("axis")
+                .help(
+                    "World axis to color by. Only used for 'colored_by_axis'; the domain is \
+                     taken from --colormap_min/--colormap_max and the colors from --colormap.",
+                )
+                .long("axis")
+                .takes_value(true)
+                .possible_values(&Axis::variants())
+                .default_value("z"),
+            clap::Arg::with_name("max_slope")
+                .help(
+                    "Slope in meters of height change per pixel above which a pixel is fully \
+                     saturated. Only used for 'colored_by_slope'.",
+                )
+                .long("max_slope")
+                .takes_value(true)
+                .required_if("coloring_strategy", "colored_by_slope"),
+            clap::Arg::with_name("auto_range")
+                .help(
+                    "Instead of using --min_intensity/--max_intensity/--max_stddev/\
+                     --colormap_min/--colormap_max as given, perform a first pass over the \
+                     points in the bounding box and derive the color domain from \
+                     --clip_percentile instead.",
+                )
+                .long("auto_range"),
+            clap::Arg::with_name("clip_percentile")
+                .help(
+                    "Percentile clipped off each end of the scalar distribution when \
+                     --auto_range is set, so a few outliers don't wash out the tile.",
+                )
+                .long("clip_percentile")
+                .takes_value(true)
+                .default_value("2.0"),
+            clap::Arg::with_name("fill_radius")
+                .help(
+                    "Radius in pixels of a morphological dilation applied to the tile before it \
+                     is written: every pixel still equal to the background color is replaced by \
+                     the nearest real pixel within this radius, filling small inter-point gaps. \
+                     0 disables the filter.",
+                )
+                .long("fill_radius")
+                .takes_value(true)
+                .default_value("0"),
+            clap::Arg::with_name("blur_sigma")
+                .help(
+                    "Standard deviation in pixels of a Gaussian blur applied to the tile after \
+                     --fill_radius, smoothing residual aliasing. 0 disables the filter.",
+                )
+                .long("blur_sigma")
                 .takes_value(true)
-                .required_if("coloring_strategy", "colored_with_height_stddev"),
+                .default_value("0"),
             clap::Arg::with_name("octree_locations")
                 .help("Octree locations to turn into xrays.")
                 .index(1)
@@ -90,13 +173,32 @@ fn parse_arguments() -> clap::ArgMatches<'static> {
         .get_matches()
 }
 
+/// Exits with a clap-style usage error unless `name` was given on the command line or
+/// `auto_range` lets the coloring strategy derive its domain instead.
+fn require_unless_auto_range(matches: &clap::ArgMatches, name: &str, auto_range: bool) {
+    if !auto_range && !matches.is_present(name) {
+        clap::Error::with_description(
+            &format!(
+                "The argument '--{}' is required unless '--auto_range' is set",
+                name
+            ),
+            clap::ErrorKind::MissingRequiredArgument,
+        )
+        .exit();
+    }
+}
+
 fn run(
     octree_locations: &[String],
     output_filename: &Path,
     resolution: f64,
-    coloring_strategy_kind: &ColoringStrategyKind,
+    coloring_strategy_kind: ColoringStrategyKind,
     tile_background_color: Color<u8>,
     bbox2: &Aabb2<f64>,
+    auto_range: bool,
+    clip_percentile: f64,
+    fill_radius: u32,
+    blur_sigma: f64,
 ) -> Result<(), Box<Error>> {
     let octree_factory = OctreeFactory::new().register("grpc://", octree_from_grpc_address);
     let point_cloud_client = PointCloudClient::new(octree_locations, octree_factory)?;
@@ -113,25 +215,46 @@ fn run(
             bbox3.max().z,
         ),
     );
+    let coloring_strategy_kind = if auto_range {
+        with_auto_range(
+            &point_cloud_client,
+            &None,
+            &bbox3,
+            clip_percentile,
+            coloring_strategy_kind,
+        )
+    } else {
+        coloring_strategy_kind
+    };
     let image_width = (bbox2.dim().x / resolution).ceil() as u32;
     let image_height = (bbox2.dim().y / resolution).ceil() as u32;
-    if !xray_from_points(
+    let image = xray_image_from_points(
         &point_cloud_client,
         &None,
         &bbox3,
-        output_filename,
         Vector2::new(image_width, image_height),
         coloring_strategy_kind.new_strategy(),
         tile_background_color,
-    ) {
-        println!("No points in bounding box. No output written.");
-    }
+    );
+    let mut image = match image {
+        Some(image) => image,
+        None => {
+            println!("No points in bounding box. No output written.");
+            return Ok(());
+        }
+    };
+    filters::fill_gaps(&mut image, tile_background_color, fill_radius);
+    filters::blur(&mut image, tile_background_color, blur_sigma);
+    image
+        .save(output_filename)
+        .expect("Could not write output PNG file.");
     Ok(())
 }
 
 pub fn main() {
     let matches = parse_arguments();
     let resolution = value_t!(matches, "resolution", f64).expect("resolution could not be parsed.");
+    let auto_range = matches.is_present("auto_range");
     let coloring_strategy_kind = {
         use crate::ColoringStrategyArgument::*;
         let arg = value_t!(matches, "coloring_strategy", ColoringStrategyArgument)
@@ -139,13 +262,45 @@ pub fn main() {
         match arg {
             xray => ColoringStrategyKind::XRay,
             colored => ColoringStrategyKind::Colored,
-            colored_with_intensity => ColoringStrategyKind::ColoredWithIntensity(
-                value_t!(matches, "min_intensity", f32).unwrap_or(1.),
-                value_t!(matches, "max_intensity", f32).unwrap_or(1.),
-            ),
-            colored_with_height_stddev => ColoringStrategyKind::ColoredWithHeightStddev(
-                value_t!(matches, "max_stddev", f32).unwrap_or(1.),
-            ),
+            colored_with_intensity => {
+                require_unless_auto_range(&matches, "min_intensity", auto_range);
+                require_unless_auto_range(&matches, "max_intensity", auto_range);
+                ColoringStrategyKind::ColoredWithIntensity(
+                    value_t!(matches, "min_intensity", f32).unwrap_or(1.),
+                    value_t!(matches, "max_intensity", f32).unwrap_or(1.),
+                )
+            }
+            colored_with_height_stddev => {
+                require_unless_auto_range(&matches, "max_stddev", auto_range);
+                ColoringStrategyKind::ColoredWithHeightStddev(
+                    value_t!(matches, "max_stddev", f32).unwrap_or(1.),
+                )
+            }
+            colored_with_colormap => {
+                require_unless_auto_range(&matches, "colormap_min", auto_range);
+                require_unless_auto_range(&matches, "colormap_max", auto_range);
+                ColoringStrategyKind::ColoredWithColormap {
+                    colormap: value_t!(matches, "colormap", Colormap).unwrap_or(Colormap::turbo),
+                    domain_min: value_t!(matches, "colormap_min", f32).unwrap_or(0.),
+                    domain_max: value_t!(matches, "colormap_max", f32).unwrap_or(1.),
+                    source: value_t!(matches, "colormap_source", ColormapSourceArgument)
+                        .unwrap_or(ColormapSourceArgument::height),
+                }
+            }
+            colored_by_axis => {
+                require_unless_auto_range(&matches, "colormap_min", auto_range);
+                require_unless_auto_range(&matches, "colormap_max", auto_range);
+                ColoringStrategyKind::ColoredByAxis {
+                    axis: value_t!(matches, "axis", Axis).unwrap_or(Axis::z),
+                    colormap: value_t!(matches, "colormap", Colormap).unwrap_or(Colormap::turbo),
+                    domain_min: value_t!(matches, "colormap_min", f32).unwrap_or(0.),
+                    domain_max: value_t!(matches, "colormap_max", f32).unwrap_or(1.),
+                }
+            }
+            colored_by_slope => ColoringStrategyKind::ColoredBySlope {
+                colormap: value_t!(matches, "colormap", Colormap).unwrap_or(Colormap::turbo),
+                max_slope: value_t!(matches, "max_slope", f32).unwrap_or(1.),
+            },
         }
     };
     let tile_background_color = {
@@ -171,14 +326,25 @@ pub fn main() {
     let max_x = value_t!(matches, "max_x", f64).expect("max_x could not be parsed.");
     let max_y = value_t!(matches, "max_y", f64).expect("max_y could not be parsed.");
 
+    let clip_percentile = value_t!(matches, "clip_percentile", f64)
+        .expect("clip_percentile could not be parsed.");
+    let fill_radius =
+        value_t!(matches, "fill_radius", u32).expect("fill_radius could not be parsed.");
+    let blur_sigma =
+        value_t!(matches, "blur_sigma", f64).expect("blur_sigma could not be parsed.");
+
     let bbox2 = Aabb2::new(Point2::new(min_x, min_y), Point2::new(max_x, max_y));
     run(
         &octree_locations,
         output_filename,
         resolution,
-        &coloring_strategy_kind,
+        coloring_strategy_kind,
         tile_background_color,
         &bbox2,
+        auto_range,
+        clip_percentile,
+        fill_radius,
+        blur_sigma,
     )
     .unwrap();
 }
@@ -0,0 +1,310 @@
+//! Perceptual colormaps used to turn a scalar value into an RGB color.
+//!
+//! Each colormap is a 256 entry lookup table sampled from the published reference
+//! implementation of the colormap: `t` in `[0, 1]` is multiplied by 255 and the two
+//! neighboring entries are linearly interpolated, which keeps the ramp smooth even though
+//! the table itself is coarse.
+
+use clap::arg_enum;
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy)]
+    #[allow(non_camel_case_types)]
+    pub enum Colormap {
+        turbo,
+        viridis,
+        magma,
+        inferno,
+        plasma,
+        grayscale,
+    }
+}
+
+impl Colormap {
+    fn lut(self) -> &'static [[u8; 3]; 256] {
+        match self {
+            Colormap::turbo => &TURBO,
+            Colormap::viridis => &VIRIDIS,
+            Colormap::magma => &MAGMA,
+            Colormap::inferno => &INFERNO,
+            Colormap::plasma => &PLASMA,
+            Colormap::grayscale => &GRAYSCALE,
+        }
+    }
+
+    /// Maps `t`, which is clamped to `[0, 1]`, through this colormap, linearly interpolating
+    /// between the two nearest lookup table entries.
+    pub fn map(self, t: f32) -> [u8; 3] {
+        let t = t.max(0.0).min(1.0);
+        let lut = self.lut();
+        let scaled = t * 255.0;
+        let lower = scaled.floor() as usize;
+        let upper = scaled.ceil() as usize;
+        if lower == upper {
+            return lut[lower];
+        }
+        let frac = scaled - lower as f32;
+        let mut result = [0u8; 3];
+        for i in 0..3 {
+            let a = f32::from(lut[lower][i]);
+            let b = f32::from(lut[upper][i]);
+            result[i] = (a + (b - a) * frac).round() as u8;
+        }
+        result
+    }
+}
+
+pub(crate) const VIRIDIS: [[u8; 3]; 256] = [
+    [68, 1, 84], [68, 2, 85], [68, 4, 86], [68, 5, 87], [67, 6, 87], [67, 7, 88], [67, 9, 89], [67, 10, 90],
+    [67, 11, 91], [67, 12, 92], [67, 14, 93], [66, 15, 93], [66, 16, 94], [66, 18, 95], [66, 19, 96], [66, 20, 97],
+    [66, 21, 98], [66, 23, 99], [65, 24, 100], [65, 25, 100], [65, 26, 101], [65, 28, 102], [65, 29, 103], [65, 30, 104],
+    [65, 31, 105], [64, 33, 106], [64, 34, 106], [64, 35, 107], [64, 37, 108], [64, 38, 109], [64, 39, 110], [64, 40, 111],
+    [63, 42, 112], [63, 43, 112], [63, 44, 113], [63, 45, 114], [63, 47, 115], [63, 48, 116], [63, 49, 117], [62, 51, 118],
+    [62, 52, 119], [62, 53, 119], [62, 54, 120], [62, 56, 121], [62, 57, 122], [62, 58, 123], [62, 59, 124], [61, 61, 125],
+    [61, 62, 125], [61, 63, 126], [61, 65, 127], [61, 66, 128], [61, 67, 129], [61, 68, 130], [60, 70, 131], [60, 71, 131],
+    [60, 72, 132], [60, 73, 133], [60, 75, 134], [60, 76, 135], [60, 77, 136], [59, 79, 137], [59, 80, 137], [59, 81, 138],
+    [59, 82, 139], [58, 83, 139], [58, 84, 139], [58, 85, 139], [57, 86, 139], [57, 87, 139], [56, 88, 139], [56, 89, 139],
+    [56, 90, 139], [55, 91, 139], [55, 92, 139], [54, 93, 139], [54, 94, 139], [54, 95, 139], [53, 96, 139], [53, 97, 139],
+    [52, 98, 139], [52, 99, 139], [52, 100, 139], [51, 101, 139], [51, 102, 139], [50, 103, 139], [50, 104, 139], [50, 105, 139],
+    [49, 106, 139], [49, 107, 139], [48, 108, 139], [48, 109, 139], [47, 109, 139], [47, 110, 139], [47, 111, 139], [46, 112, 139],
+    [46, 113, 140], [45, 114, 140], [45, 115, 140], [45, 116, 140], [44, 117, 140], [44, 118, 140], [43, 119, 140], [43, 120, 140],
+    [43, 121, 140], [42, 122, 140], [42, 123, 140], [41, 124, 140], [41, 125, 140], [41, 126, 140], [40, 127, 140], [40, 128, 140],
+    [39, 129, 140], [39, 130, 140], [39, 131, 140], [38, 132, 140], [38, 133, 140], [37, 134, 140], [37, 135, 140], [36, 136, 140],
+    [36, 137, 140], [36, 138, 140], [35, 139, 140], [35, 140, 140], [34, 141, 140], [34, 142, 140], [34, 143, 140], [33, 144, 140],
+    [33, 144, 140], [34, 145, 139], [35, 146, 138], [36, 147, 138], [37, 148, 137], [38, 149, 136], [39, 150, 136], [40, 151, 135],
+    [41, 152, 135], [42, 152, 134], [43, 153, 133], [44, 154, 133], [45, 155, 132], [46, 156, 131], [47, 157, 131], [48, 158, 130],
+    [49, 159, 129], [49, 160, 129], [50, 161, 128], [51, 161, 127], [52, 162, 127], [53, 163, 126], [54, 164, 126], [55, 165, 125],
+    [56, 166, 124], [57, 167, 124], [58, 168, 123], [59, 169, 122], [60, 169, 122], [61, 170, 121], [62, 171, 120], [63, 172, 120],
+    [64, 173, 119], [65, 174, 118], [65, 175, 118], [66, 176, 117], [67, 177, 117], [68, 178, 116], [69, 178, 115], [70, 179, 115],
+    [71, 180, 114], [72, 181, 113], [73, 182, 113], [74, 183, 112], [75, 184, 111], [76, 185, 111], [77, 186, 110], [78, 186, 109],
+    [79, 187, 109], [80, 188, 108], [81, 189, 108], [81, 190, 107], [82, 191, 106], [83, 192, 106], [84, 193, 105], [85, 194, 104],
+    [86, 195, 104], [87, 195, 103], [88, 196, 102], [89, 197, 102], [90, 198, 101], [91, 199, 100], [92, 200, 100], [93, 201, 99],
+    [95, 201, 98], [97, 202, 97], [100, 202, 96], [102, 203, 95], [105, 203, 94], [107, 204, 93], [110, 204, 92], [112, 205, 91],
+    [115, 205, 90], [117, 206, 90], [120, 206, 89], [122, 207, 88], [125, 207, 87], [128, 207, 86], [130, 208, 85], [133, 208, 84],
+    [135, 209, 83], [138, 209, 82], [140, 210, 81], [143, 210, 80], [145, 211, 79], [148, 211, 78], [150, 212, 77], [153, 212, 76],
+    [155, 213, 75], [158, 213, 74], [160, 214, 73], [163, 214, 72], [165, 215, 71], [168, 215, 70], [170, 215, 69], [173, 216, 68],
+    [175, 216, 67], [178, 217, 66], [180, 217, 65], [183, 218, 64], [185, 218, 63], [188, 219, 62], [190, 219, 61], [193, 220, 60],
+    [195, 220, 59], [198, 221, 58], [200, 221, 57], [203, 222, 56], [205, 222, 55], [208, 223, 55], [210, 223, 54], [213, 223, 53],
+    [215, 224, 52], [218, 224, 51], [220, 225, 50], [223, 225, 49], [225, 226, 48], [228, 226, 47], [230, 227, 46], [233, 227, 45],
+    [235, 228, 44], [238, 228, 43], [240, 229, 42], [243, 229, 41], [245, 230, 40], [248, 230, 39], [250, 231, 38], [253, 231, 37],
+];
+
+pub(crate) const MAGMA: [[u8; 3]; 256] = [
+    [0, 0, 4], [1, 0, 6], [3, 1, 8], [4, 1, 10], [5, 1, 12], [6, 1, 13], [8, 2, 15], [9, 2, 17],
+    [10, 2, 19], [11, 3, 21], [13, 3, 23], [14, 3, 25], [15, 3, 27], [17, 4, 28], [18, 4, 30], [19, 4, 32],
+    [20, 5, 34], [22, 5, 36], [23, 5, 38], [24, 5, 40], [25, 6, 42], [27, 6, 44], [28, 6, 45], [29, 6, 47],
+    [30, 7, 49], [32, 7, 51], [33, 7, 53], [34, 8, 55], [36, 8, 57], [37, 8, 59], [38, 8, 60], [39, 9, 62],
+    [41, 9, 64], [42, 9, 66], [43, 10, 68], [44, 10, 70], [46, 10, 72], [47, 10, 74], [48, 11, 76], [50, 11, 77],
+    [51, 11, 79], [52, 12, 81], [53, 12, 83], [55, 12, 85], [56, 12, 87], [57, 13, 89], [58, 13, 91], [60, 13, 92],
+    [61, 14, 94], [62, 14, 96], [64, 14, 98], [65, 14, 100], [66, 15, 102], [67, 15, 104], [69, 15, 106], [70, 16, 108],
+    [71, 16, 109], [72, 16, 111], [74, 16, 113], [75, 17, 115], [76, 17, 117], [78, 17, 119], [79, 18, 121], [80, 18, 123],
+    [81, 18, 124], [83, 19, 124], [85, 19, 124], [86, 20, 124], [88, 20, 124], [89, 21, 124], [91, 22, 124], [93, 22, 124],
+    [94, 23, 124], [96, 23, 124], [97, 24, 124], [99, 25, 123], [101, 25, 123], [102, 26, 123], [104, 26, 123], [105, 27, 123],
+    [107, 27, 123], [109, 28, 123], [110, 29, 123], [112, 29, 123], [113, 30, 123], [115, 30, 123], [117, 31, 123], [118, 31, 123],
+    [120, 32, 123], [121, 33, 123], [123, 33, 123], [125, 34, 123], [126, 34, 123], [128, 35, 123], [129, 36, 123], [131, 36, 123],
+    [133, 37, 122], [134, 37, 122], [136, 38, 122], [137, 38, 122], [139, 39, 122], [141, 40, 122], [142, 40, 122], [144, 41, 122],
+    [145, 41, 122], [147, 42, 122], [149, 43, 122], [150, 43, 122], [152, 44, 122], [153, 44, 122], [155, 45, 122], [157, 45, 122],
+    [158, 46, 122], [160, 47, 122], [161, 47, 122], [163, 48, 122], [165, 48, 122], [166, 49, 121], [168, 49, 121], [169, 50, 121],
+    [171, 51, 121], [173, 51, 121], [174, 52, 121], [176, 52, 121], [177, 53, 121], [179, 54, 121], [181, 54, 121], [182, 55, 121],
+    [184, 56, 121], [185, 57, 120], [186, 58, 120], [187, 60, 120], [188, 61, 119], [189, 62, 119], [190, 63, 119], [191, 65, 118],
+    [192, 66, 118], [193, 67, 117], [194, 69, 117], [195, 70, 117], [197, 71, 116], [198, 72, 116], [199, 74, 116], [200, 75, 115],
+    [201, 76, 115], [202, 78, 114], [203, 79, 114], [204, 80, 114], [205, 81, 113], [206, 83, 113], [207, 84, 113], [208, 85, 112],
+    [210, 87, 112], [211, 88, 111], [212, 89, 111], [213, 90, 111], [214, 92, 110], [215, 93, 110], [216, 94, 110], [217, 96, 109],
+    [218, 97, 109], [219, 98, 108], [220, 99, 108], [221, 101, 108], [223, 102, 107], [224, 103, 107], [225, 105, 107], [226, 106, 106],
+    [227, 107, 106], [228, 108, 105], [229, 110, 105], [230, 111, 105], [231, 112, 104], [232, 114, 104], [233, 115, 103], [234, 116, 103],
+    [235, 117, 103], [237, 119, 102], [238, 120, 102], [239, 121, 102], [240, 123, 101], [241, 124, 101], [242, 125, 100], [243, 126, 100],
+    [244, 128, 100], [245, 129, 99], [246, 130, 99], [247, 132, 99], [248, 133, 98], [250, 134, 98], [251, 135, 97], [252, 137, 97],
+    [252, 138, 98], [252, 140, 100], [252, 142, 101], [252, 144, 103], [252, 146, 104], [252, 147, 105], [252, 149, 107], [252, 151, 108],
+    [252, 153, 110], [252, 155, 111], [252, 157, 113], [252, 158, 114], [252, 160, 116], [252, 162, 117], [252, 164, 119], [252, 166, 120],
+    [252, 167, 122], [252, 169, 123], [252, 171, 125], [252, 173, 126], [252, 175, 128], [252, 177, 129], [252, 178, 131], [252, 180, 132],
+    [252, 182, 133], [252, 184, 135], [252, 186, 136], [252, 187, 138], [252, 189, 139], [252, 191, 141], [252, 193, 142], [252, 195, 144],
+    [252, 197, 145], [252, 198, 147], [252, 200, 148], [252, 202, 150], [252, 204, 151], [252, 206, 153], [252, 208, 154], [252, 209, 156],
+    [252, 211, 157], [252, 213, 159], [252, 215, 160], [252, 217, 162], [252, 218, 163], [252, 220, 164], [252, 222, 166], [252, 224, 167],
+    [252, 226, 169], [252, 228, 170], [252, 229, 172], [252, 231, 173], [252, 233, 175], [252, 235, 176], [252, 237, 178], [252, 238, 179],
+    [252, 240, 181], [252, 242, 182], [252, 244, 184], [252, 246, 185], [252, 248, 187], [252, 249, 188], [252, 251, 190], [252, 253, 191],
+];
+
+pub(crate) const INFERNO: [[u8; 3]; 256] = [
+    [0, 0, 4], [1, 0, 6], [3, 1, 7], [4, 1, 9], [5, 1, 11], [7, 1, 12], [8, 2, 14], [10, 2, 16],
+    [11, 2, 17], [12, 2, 19], [14, 3, 21], [15, 3, 22], [16, 3, 24], [18, 3, 26], [19, 4, 27], [20, 4, 29],
+    [22, 4, 31], [23, 4, 32], [25, 5, 34], [26, 5, 36], [27, 5, 37], [29, 5, 39], [30, 6, 41], [31, 6, 42],
+    [33, 6, 44], [34, 6, 46], [35, 7, 47], [37, 7, 49], [38, 7, 51], [40, 7, 52], [41, 8, 54], [42, 8, 56],
+    [44, 8, 57], [45, 8, 59], [46, 9, 61], [48, 9, 62], [49, 9, 64], [50, 9, 66], [52, 10, 67], [53, 10, 69],
+    [55, 10, 71], [56, 10, 72], [57, 11, 74], [59, 11, 75], [60, 11, 77], [61, 11, 79], [63, 12, 80], [64, 12, 82],
+    [66, 12, 84], [67, 12, 85], [68, 13, 87], [70, 13, 89], [71, 13, 90], [72, 13, 92], [74, 14, 94], [75, 14, 95],
+    [76, 14, 97], [78, 14, 99], [79, 15, 100], [81, 15, 102], [82, 15, 104], [83, 15, 105], [85, 16, 107], [86, 16, 109],
+    [87, 16, 110], [89, 17, 109], [91, 17, 109], [92, 18, 109], [94, 19, 108], [95, 19, 108], [97, 20, 107], [98, 20, 107],
+    [100, 21, 107], [102, 22, 106], [103, 22, 106], [105, 23, 105], [106, 23, 105], [108, 24, 105], [110, 25, 104], [111, 25, 104],
+    [113, 26, 103], [114, 27, 103], [116, 27, 103], [117, 28, 102], [119, 28, 102], [121, 29, 101], [122, 30, 101], [124, 30, 101],
+    [125, 31, 100], [127, 31, 100], [129, 32, 99], [130, 33, 99], [132, 33, 98], [133, 34, 98], [135, 35, 98], [137, 35, 97],
+    [138, 36, 97], [140, 36, 96], [141, 37, 96], [143, 38, 96], [144, 38, 95], [146, 39, 95], [148, 39, 94], [149, 40, 94],
+    [151, 41, 94], [152, 41, 93], [154, 42, 93], [156, 42, 92], [157, 43, 92], [159, 44, 92], [160, 44, 91], [162, 45, 91],
+    [163, 46, 90], [165, 46, 90], [167, 47, 90], [168, 47, 89], [170, 48, 89], [171, 49, 88], [173, 49, 88], [175, 50, 87],
+    [176, 50, 87], [178, 51, 87], [179, 52, 86], [181, 52, 86], [182, 53, 85], [184, 53, 85], [186, 54, 85], [187, 55, 84],
+    [188, 56, 83], [189, 57, 82], [190, 58, 81], [191, 60, 80], [192, 61, 79], [193, 63, 78], [194, 64, 76], [195, 65, 75],
+    [196, 67, 74], [197, 68, 73], [198, 69, 72], [199, 71, 70], [200, 72, 69], [201, 73, 68], [202, 75, 67], [203, 76, 66],
+    [204, 78, 65], [205, 79, 63], [206, 80, 62], [207, 82, 61], [208, 83, 60], [209, 84, 59], [210, 86, 58], [210, 87, 56],
+    [211, 88, 55], [212, 90, 54], [213, 91, 53], [214, 93, 52], [215, 94, 50], [216, 95, 49], [217, 97, 48], [218, 98, 47],
+    [219, 99, 46], [220, 101, 45], [221, 102, 43], [222, 103, 42], [223, 105, 41], [224, 106, 40], [225, 108, 39], [226, 109, 38],
+    [227, 110, 36], [228, 112, 35], [229, 113, 34], [230, 114, 33], [231, 116, 32], [232, 117, 30], [232, 118, 29], [233, 120, 28],
+    [234, 121, 27], [235, 123, 26], [236, 124, 25], [237, 125, 23], [238, 127, 22], [239, 128, 21], [240, 129, 20], [241, 131, 19],
+    [242, 132, 18], [243, 133, 16], [244, 135, 15], [245, 136, 14], [246, 138, 13], [247, 139, 12], [248, 140, 10], [249, 142, 9],
+    [249, 143, 11], [249, 145, 13], [249, 147, 16], [249, 149, 18], [249, 150, 21], [249, 152, 23], [249, 154, 25], [249, 156, 28],
+    [249, 158, 30], [249, 159, 33], [250, 161, 35], [250, 163, 38], [250, 165, 40], [250, 166, 42], [250, 168, 45], [250, 170, 47],
+    [250, 172, 50], [250, 173, 52], [250, 175, 55], [250, 177, 57], [250, 179, 59], [250, 181, 62], [250, 182, 64], [250, 184, 67],
+    [250, 186, 69], [250, 188, 72], [250, 189, 74], [250, 191, 76], [250, 193, 79], [250, 195, 81], [250, 197, 84], [250, 198, 86],
+    [251, 200, 89], [251, 202, 91], [251, 204, 93], [251, 205, 96], [251, 207, 98], [251, 209, 101], [251, 211, 103], [251, 212, 106],
+    [251, 214, 108], [251, 216, 111], [251, 218, 113], [251, 220, 115], [251, 221, 118], [251, 223, 120], [251, 225, 123], [251, 227, 125],
+    [251, 228, 128], [251, 230, 130], [251, 232, 132], [251, 234, 135], [251, 236, 137], [252, 237, 140], [252, 239, 142], [252, 241, 145],
+    [252, 243, 147], [252, 244, 149], [252, 246, 152], [252, 248, 154], [252, 250, 157], [252, 251, 159], [252, 253, 162], [252, 255, 164],
+];
+
+pub(crate) const PLASMA: [[u8; 3]; 256] = [
+    [13, 8, 135], [15, 8, 136], [17, 8, 136], [18, 8, 137], [20, 8, 137], [22, 8, 138], [24, 8, 138], [25, 7, 139],
+    [27, 7, 139], [29, 7, 140], [31, 7, 140], [32, 7, 141], [34, 7, 141], [36, 7, 142], [38, 7, 142], [40, 7, 143],
+    [41, 7, 143], [43, 7, 144], [45, 7, 144], [47, 7, 145], [48, 6, 145], [50, 6, 146], [52, 6, 146], [54, 6, 147],
+    [56, 6, 147], [57, 6, 148], [59, 6, 148], [61, 6, 149], [63, 6, 149], [64, 6, 150], [66, 6, 151], [68, 6, 151],
+    [70, 5, 152], [71, 5, 152], [73, 5, 153], [75, 5, 153], [77, 5, 154], [79, 5, 154], [80, 5, 155], [82, 5, 155],
+    [84, 5, 156], [86, 5, 156], [87, 5, 157], [89, 5, 157], [91, 5, 158], [93, 4, 158], [95, 4, 159], [96, 4, 159],
+    [98, 4, 160], [100, 4, 160], [102, 4, 161], [103, 4, 161], [105, 4, 162], [107, 4, 162], [109, 4, 163], [110, 4, 163],
+    [112, 4, 164], [114, 4, 165], [116, 3, 165], [118, 3, 166], [119, 3, 166], [121, 3, 167], [123, 3, 167], [125, 3, 168],
+    [126, 3, 168], [128, 4, 167], [129, 5, 166], [130, 6, 166], [131, 8, 165], [132, 9, 164], [134, 10, 163], [135, 11, 163],
+    [136, 12, 162], [137, 13, 161], [139, 14, 160], [140, 15, 160], [141, 16, 159], [142, 17, 158], [143, 18, 157], [145, 19, 157],
+    [146, 20, 156], [147, 21, 155], [148, 22, 154], [150, 24, 154], [151, 25, 153], [152, 26, 152], [153, 27, 151], [154, 28, 150],
+    [156, 29, 150], [157, 30, 149], [158, 31, 148], [159, 32, 147], [161, 33, 147], [162, 34, 146], [163, 35, 145], [164, 36, 144],
+    [165, 37, 144], [167, 38, 143], [168, 40, 142], [169, 41, 141], [170, 42, 141], [172, 43, 140], [173, 44, 139], [174, 45, 138],
+    [175, 46, 138], [176, 47, 137], [178, 48, 136], [179, 49, 135], [180, 50, 135], [181, 51, 134], [183, 52, 133], [184, 53, 132],
+    [185, 54, 132], [186, 56, 131], [187, 57, 130], [189, 58, 129], [190, 59, 129], [191, 60, 128], [192, 61, 127], [194, 62, 126],
+    [195, 63, 126], [196, 64, 125], [197, 65, 124], [198, 66, 123], [200, 67, 123], [201, 68, 122], [202, 69, 121], [203, 70, 120],
+    [204, 72, 120], [205, 73, 119], [206, 74, 118], [206, 75, 117], [207, 77, 116], [208, 78, 115], [208, 79, 114], [209, 80, 113],
+    [210, 81, 113], [211, 83, 112], [211, 84, 111], [212, 85, 110], [213, 86, 109], [213, 88, 108], [214, 89, 107], [215, 90, 106],
+    [215, 91, 106], [216, 92, 105], [217, 94, 104], [217, 95, 103], [218, 96, 102], [219, 97, 101], [220, 99, 100], [220, 100, 99],
+    [221, 101, 98], [222, 102, 98], [222, 103, 97], [223, 105, 96], [224, 106, 95], [224, 107, 94], [225, 108, 93], [226, 110, 92],
+    [226, 111, 91], [227, 112, 91], [228, 113, 90], [229, 114, 89], [229, 116, 88], [230, 117, 87], [231, 118, 86], [231, 119, 85],
+    [232, 121, 84], [233, 122, 84], [233, 123, 83], [234, 124, 82], [235, 125, 81], [235, 127, 80], [236, 128, 79], [237, 129, 78],
+    [237, 130, 77], [238, 132, 77], [239, 133, 76], [240, 134, 75], [240, 135, 74], [241, 136, 73], [242, 138, 72], [242, 139, 71],
+    [243, 140, 70], [244, 141, 69], [244, 143, 69], [245, 144, 68], [246, 145, 67], [246, 146, 66], [247, 147, 65], [248, 149, 64],
+    [248, 150, 64], [248, 152, 63], [248, 153, 63], [248, 155, 62], [247, 156, 62], [247, 158, 61], [247, 160, 61], [247, 161, 60],
+    [247, 163, 60], [247, 164, 59], [247, 166, 59], [247, 167, 58], [246, 169, 58], [246, 171, 57], [246, 172, 57], [246, 174, 56],
+    [246, 175, 56], [246, 177, 55], [246, 178, 55], [246, 180, 54], [245, 182, 54], [245, 183, 53], [245, 185, 53], [245, 186, 52],
+    [245, 188, 52], [245, 189, 51], [245, 191, 51], [245, 193, 51], [244, 194, 50], [244, 196, 50], [244, 197, 49], [244, 199, 49],
+    [244, 200, 48], [244, 202, 48], [244, 204, 47], [244, 205, 47], [243, 207, 46], [243, 208, 46], [243, 210, 45], [243, 211, 45],
+    [243, 213, 44], [243, 214, 44], [243, 216, 43], [243, 218, 43], [242, 219, 42], [242, 221, 42], [242, 222, 41], [242, 224, 41],
+    [242, 225, 40], [242, 227, 40], [242, 229, 39], [242, 230, 39], [241, 232, 38], [241, 233, 38], [241, 235, 37], [241, 236, 37],
+    [241, 238, 36], [241, 240, 36], [241, 241, 35], [241, 243, 35], [240, 244, 34], [240, 246, 34], [240, 247, 33], [240, 249, 33],
+];
+
+pub(crate) const TURBO: [[u8; 3]; 256] = [
+    [35, 23, 27], [39, 26, 40], [43, 28, 52], [47, 30, 63], [51, 32, 74], [54, 35, 85], [57, 37, 95], [59, 40, 105],
+    [62, 42, 114], [64, 44, 123], [66, 47, 132], [68, 49, 140], [69, 52, 148], [71, 55, 155], [72, 57, 162], [73, 60, 169],
+    [73, 62, 175], [74, 65, 181], [74, 68, 187], [75, 70, 193], [75, 73, 198], [75, 76, 203], [75, 79, 207], [74, 81, 211],
+    [74, 84, 215], [74, 87, 219], [73, 89, 223], [73, 92, 226], [72, 95, 229], [71, 98, 232], [70, 101, 234], [69, 103, 237],
+    [68, 106, 239], [67, 109, 240], [66, 112, 242], [65, 114, 244], [64, 117, 245], [63, 120, 246], [62, 123, 247], [61, 125, 248],
+    [59, 128, 248], [58, 131, 249], [57, 134, 249], [56, 136, 249], [55, 139, 249], [53, 142, 249], [52, 144, 248], [51, 147, 248],
+    [50, 150, 247], [49, 152, 246], [48, 155, 246], [47, 158, 245], [46, 160, 244], [45, 163, 242], [44, 165, 241], [43, 168, 240],
+    [42, 170, 238], [42, 173, 237], [41, 175, 235], [40, 178, 234], [40, 180, 232], [39, 182, 230], [39, 185, 228], [38, 187, 226],
+    [38, 189, 224], [37, 192, 222], [37, 194, 220], [37, 196, 218], [37, 198, 215], [37, 200, 213], [37, 202, 211], [37, 205, 209],
+    [37, 207, 206], [38, 209, 204], [38, 210, 201], [38, 212, 199], [39, 214, 196], [39, 216, 194], [40, 218, 191], [41, 220, 189],
+    [42, 221, 186], [43, 223, 184], [44, 225, 181], [45, 226, 178], [46, 228, 176], [47, 229, 173], [48, 231, 171], [49, 232, 168],
+    [51, 234, 166], [52, 235, 163], [54, 236, 160], [55, 238, 158], [57, 239, 155], [59, 240, 153], [61, 241, 150], [63, 242, 148],
+    [65, 243, 145], [67, 244, 143], [69, 245, 140], [71, 246, 138], [73, 247, 135], [75, 248, 133], [78, 249, 131], [80, 249, 128],
+    [82, 250, 126], [85, 250, 124], [87, 251, 121], [90, 251, 119], [93, 252, 117], [95, 252, 115], [98, 253, 113], [101, 253, 110],
+    [104, 253, 108], [106, 253, 106], [109, 254, 104], [112, 254, 102], [115, 254, 100], [118, 254, 98], [121, 254, 96], [124, 253, 94],
+    [127, 253, 93], [130, 253, 91], [133, 253, 89], [136, 252, 87], [139, 252, 86], [142, 252, 84], [145, 251, 82], [149, 251, 81],
+    [152, 250, 79], [155, 249, 78], [158, 249, 76], [161, 248, 75], [164, 247, 73], [167, 246, 72], [170, 246, 70], [173, 245, 69],
+    [176, 244, 68], [179, 243, 66], [182, 242, 65], [185, 240, 64], [188, 239, 63], [191, 238, 62], [194, 237, 60], [197, 235, 59],
+    [200, 234, 58], [203, 233, 57], [205, 231, 56], [208, 230, 55], [211, 228, 54], [213, 227, 53], [216, 225, 52], [219, 223, 52],
+    [221, 222, 51], [223, 220, 50], [226, 218, 49], [228, 216, 48], [230, 214, 48], [233, 212, 47], [235, 210, 46], [237, 208, 45],
+    [239, 206, 45], [241, 204, 44], [243, 202, 43], [244, 200, 43], [246, 198, 42], [248, 196, 42], [249, 193, 41], [251, 191, 40],
+    [252, 189, 40], [253, 186, 39], [255, 184, 39], [255, 181, 38], [255, 179, 38], [255, 177, 37], [255, 174, 37], [255, 172, 36],
+    [255, 169, 36], [255, 166, 35], [255, 164, 35], [255, 161, 34], [255, 159, 34], [255, 156, 34], [255, 153, 33], [255, 151, 33],
+    [255, 148, 32], [255, 145, 32], [255, 142, 31], [255, 140, 31], [255, 137, 30], [255, 134, 30], [255, 131, 30], [255, 129, 29],
+    [255, 126, 29], [255, 123, 28], [255, 120, 28], [255, 117, 27], [255, 115, 27], [255, 112, 26], [254, 109, 26], [252, 106, 26],
+    [251, 104, 25], [249, 101, 25], [248, 98, 24], [246, 95, 24], [244, 92, 23], [243, 90, 23], [241, 87, 22], [239, 84, 22],
+    [237, 82, 21], [235, 79, 20], [233, 76, 20], [230, 74, 19], [228, 71, 19], [226, 69, 18], [224, 66, 18], [221, 64, 17],
+    [219, 61, 16], [216, 59, 16], [214, 56, 15], [211, 54, 15], [209, 52, 14], [206, 49, 13], [203, 47, 13], [201, 45, 12],
+    [198, 43, 11], [196, 41, 11], [193, 39, 10], [190, 37, 10], [188, 35, 9], [185, 33, 8], [183, 31, 8], [180, 29, 7],
+    [177, 28, 6], [175, 26, 6], [172, 24, 5], [170, 23, 4], [168, 22, 4], [165, 20, 3], [163, 19, 2], [161, 18, 2],
+    [159, 17, 1], [157, 16, 0], [155, 15, 0], [154, 14, 0], [152, 14, 0], [150, 13, 0], [149, 12, 0], [148, 12, 0],
+    [147, 12, 0], [146, 12, 0], [145, 11, 0], [145, 12, 0], [144, 12, 0], [144, 12, 0], [144, 12, 0], [144, 13, 0],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Colormap; 6] = [
+        Colormap::turbo,
+        Colormap::viridis,
+        Colormap::magma,
+        Colormap::inferno,
+        Colormap::plasma,
+        Colormap::grayscale,
+    ];
+
+    /// A real sampled colormap can legitimately repeat an entry once or twice where the curve is
+    /// near-stationary (e.g. close to a local min/max), but should never contain a long run of
+    /// exactly identical neighboring entries: that is the signature of a corrupted or truncated
+    /// lookup table (the original bug this guards against had 60 identical entries in a row),
+    /// not a smooth ramp. This must compare for exact equality, not "near" equality within some
+    /// tolerance: a smooth ramp drifts by a roughly constant amount per entry, so a tolerance
+    /// loose enough to allow that drift also fails to distinguish it from a genuine flat run.
+    #[test]
+    fn luts_have_no_long_flat_runs() {
+        const MAX_ALLOWED_RUN: usize = 6;
+        for colormap in ALL {
+            let lut = colormap.lut();
+            let mut run = 1;
+            let mut max_run = 1;
+            for i in 1..lut.len() {
+                if lut[i] == lut[i - 1] {
+                    run += 1;
+                    max_run = max_run.max(run);
+                } else {
+                    run = 1;
+                }
+            }
+            assert!(
+                max_run <= MAX_ALLOWED_RUN,
+                "{:?} has a run of {} identical entries",
+                colormap,
+                max_run
+            );
+        }
+    }
+}
+
+pub(crate) const GRAYSCALE: [[u8; 3]; 256] = [
+    [0, 0, 0], [1, 1, 1], [2, 2, 2], [3, 3, 3], [4, 4, 4], [5, 5, 5], [6, 6, 6], [7, 7, 7],
+    [8, 8, 8], [9, 9, 9], [10, 10, 10], [11, 11, 11], [12, 12, 12], [13, 13, 13], [14, 14, 14], [15, 15, 15],
+    [16, 16, 16], [17, 17, 17], [18, 18, 18], [19, 19, 19], [20, 20, 20], [21, 21, 21], [22, 22, 22], [23, 23, 23],
+    [24, 24, 24], [25, 25, 25], [26, 26, 26], [27, 27, 27], [28, 28, 28], [29, 29, 29], [30, 30, 30], [31, 31, 31],
+    [32, 32, 32], [33, 33, 33], [34, 34, 34], [35, 35, 35], [36, 36, 36], [37, 37, 37], [38, 38, 38], [39, 39, 39],
+    [40, 40, 40], [41, 41, 41], [42, 42, 42], [43, 43, 43], [44, 44, 44], [45, 45, 45], [46, 46, 46], [47, 47, 47],
+    [48, 48, 48], [49, 49, 49], [50, 50, 50], [51, 51, 51], [52, 52, 52], [53, 53, 53], [54, 54, 54], [55, 55, 55],
+    [56, 56, 56], [57, 57, 57], [58, 58, 58], [59, 59, 59], [60, 60, 60], [61, 61, 61], [62, 62, 62], [63, 63, 63],
+    [64, 64, 64], [65, 65, 65], [66, 66, 66], [67, 67, 67], [68, 68, 68], [69, 69, 69], [70, 70, 70], [71, 71, 71],
+    [72, 72, 72], [73, 73, 73], [74, 74, 74], [75, 75, 75], [76, 76, 76], [77, 77, 77], [78, 78, 78], [79, 79, 79],
+    [80, 80, 80], [81, 81, 81], [82, 82, 82], [83, 83, 83], [84, 84, 84], [85, 85, 85], [86, 86, 86], [87, 87, 87],
+    [88, 88, 88], [89, 89, 89], [90, 90, 90], [91, 91, 91], [92, 92, 92], [93, 93, 93], [94, 94, 94], [95, 95, 95],
+    [96, 96, 96], [97, 97, 97], [98, 98, 98], [99, 99, 99], [100, 100, 100], [101, 101, 101], [102, 102, 102], [103, 103, 103],
+    [104, 104, 104], [105, 105, 105], [106, 106, 106], [107, 107, 107], [108, 108, 108], [109, 109, 109], [110, 110, 110], [111, 111, 111],
+    [112, 112, 112], [113, 113, 113], [114, 114, 114], [115, 115, 115], [116, 116, 116], [117, 117, 117], [118, 118, 118], [119, 119, 119],
+    [120, 120, 120], [121, 121, 121], [122, 122, 122], [123, 123, 123], [124, 124, 124], [125, 125, 125], [126, 126, 126], [127, 127, 127],
+    [128, 128, 128], [129, 129, 129], [130, 130, 130], [131, 131, 131], [132, 132, 132], [133, 133, 133], [134, 134, 134], [135, 135, 135],
+    [136, 136, 136], [137, 137, 137], [138, 138, 138], [139, 139, 139], [140, 140, 140], [141, 141, 141], [142, 142, 142], [143, 143, 143],
+    [144, 144, 144], [145, 145, 145], [146, 146, 146], [147, 147, 147], [148, 148, 148], [149, 149, 149], [150, 150, 150], [151, 151, 151],
+    [152, 152, 152], [153, 153, 153], [154, 154, 154], [155, 155, 155], [156, 156, 156], [157, 157, 157], [158, 158, 158], [159, 159, 159],
+    [160, 160, 160], [161, 161, 161], [162, 162, 162], [163, 163, 163], [164, 164, 164], [165, 165, 165], [166, 166, 166], [167, 167, 167],
+    [168, 168, 168], [169, 169, 169], [170, 170, 170], [171, 171, 171], [172, 172, 172], [173, 173, 173], [174, 174, 174], [175, 175, 175],
+    [176, 176, 176], [177, 177, 177], [178, 178, 178], [179, 179, 179], [180, 180, 180], [181, 181, 181], [182, 182, 182], [183, 183, 183],
+    [184, 184, 184], [185, 185, 185], [186, 186, 186], [187, 187, 187], [188, 188, 188], [189, 189, 189], [190, 190, 190], [191, 191, 191],
+    [192, 192, 192], [193, 193, 193], [194, 194, 194], [195, 195, 195], [196, 196, 196], [197, 197, 197], [198, 198, 198], [199, 199, 199],
+    [200, 200, 200], [201, 201, 201], [202, 202, 202], [203, 203, 203], [204, 204, 204], [205, 205, 205], [206, 206, 206], [207, 207, 207],
+    [208, 208, 208], [209, 209, 209], [210, 210, 210], [211, 211, 211], [212, 212, 212], [213, 213, 213], [214, 214, 214], [215, 215, 215],
+    [216, 216, 216], [217, 217, 217], [218, 218, 218], [219, 219, 219], [220, 220, 220], [221, 221, 221], [222, 222, 222], [223, 223, 223],
+    [224, 224, 224], [225, 225, 225], [226, 226, 226], [227, 227, 227], [228, 228, 228], [229, 229, 229], [230, 230, 230], [231, 231, 231],
+    [232, 232, 232], [233, 233, 233], [234, 234, 234], [235, 235, 235], [236, 236, 236], [237, 237, 237], [238, 238, 238], [239, 239, 239],
+    [240, 240, 240], [241, 241, 241], [242, 242, 242], [243, 243, 243], [244, 244, 244], [245, 245, 245], [246, 246, 246], [247, 247, 247],
+    [248, 248, 248], [249, 249, 249], [250, 250, 250], [251, 251, 251], [252, 252, 252], [253, 253, 253], [254, 254, 254], [255, 255, 255],
+];
@@ -0,0 +1,252 @@
+//! Optional post-processing filters applied to a rasterized tile before it is written to disk.
+//! Both filters treat pixels still equal to the tile background color as "no data" and never
+//! let that color bleed into real data: `fill_gaps` only writes into background pixels within
+//! its radius of real data, and `blur` leaves background pixels as they are, so transparent
+//! tiles stay transparent away from the data.
+
+use image::{Rgba, RgbaImage};
+use point_viewer::color::Color;
+
+fn as_rgba(color: Color<u8>) -> Rgba<u8> {
+    Rgba([color.red, color.green, color.blue, color.alpha])
+}
+
+/// Performs a morphological dilation of `image` into the background: every pixel that is still exactly
+/// `background` is replaced by the closest non-background pixel within a disk structuring
+/// element of `radius`, filling small inter-point gaps so a surface reads as continuous. Pixels
+/// that already hold real data are never touched.
+pub fn fill_gaps(image: &mut RgbaImage, background: Color<u8>, radius: u32) {
+    if radius == 0 {
+        return;
+    }
+    let background = as_rgba(background);
+    let (width, height) = image.dimensions();
+    let original = image.clone();
+    let radius = i64::from(radius);
+    let radius_squared = radius * radius;
+    for y in 0..height {
+        for x in 0..width {
+            if *original.get_pixel(x, y) != background {
+                continue;
+            }
+            let mut closest = None;
+            for dy in -radius..=radius {
+                let ny = y as i64 + dy;
+                if ny < 0 || ny >= i64::from(height) {
+                    continue;
+                }
+                for dx in -radius..=radius {
+                    let distance_squared = dx * dx + dy * dy;
+                    if distance_squared > radius_squared {
+                        continue;
+                    }
+                    let nx = x as i64 + dx;
+                    if nx < 0 || nx >= i64::from(width) {
+                        continue;
+                    }
+                    let candidate = *original.get_pixel(nx as u32, ny as u32);
+                    if candidate == background {
+                        continue;
+                    }
+                    if closest.map_or(true, |(best, _)| distance_squared < best) {
+                        closest = Some((distance_squared, candidate));
+                    }
+                }
+            }
+            if let Some((_, color)) = closest {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+/// Applies a separable Gaussian blur with standard deviation `sigma` to `image`, smoothing
+/// residual rasterization aliasing. Pixels equal to `background` are excluded from the
+/// convolution on both sides: they are never blurred themselves, and their color never
+/// contributes to a real pixel's blurred value.
+pub fn blur(image: &mut RgbaImage, background: Color<u8>, sigma: f64) {
+    if sigma <= 0. {
+        return;
+    }
+    let background = as_rgba(background);
+    let kernel = gaussian_kernel(sigma);
+    let horizontal = convolve_horizontal(image, &kernel, background);
+    let vertical = convolve_vertical(&horizontal, &kernel, background);
+    *image = vertical;
+}
+
+/// A 1D Gaussian kernel truncated at 3 standard deviations and normalized to sum to 1.
+fn gaussian_kernel(sigma: f64) -> Vec<f64> {
+    let radius = (sigma * 3.).ceil() as i64;
+    let mut kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-(i * i) as f64 / (2. * sigma * sigma)).exp())
+        .collect();
+    let sum: f64 = kernel.iter().sum();
+    for weight in &mut kernel {
+        *weight /= sum;
+    }
+    kernel
+}
+
+fn convolve_horizontal(image: &RgbaImage, kernel: &[f64], background: Rgba<u8>) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let radius = (kernel.len() / 2) as i64;
+    let mut out = image.clone();
+    for y in 0..height {
+        for x in 0..width {
+            if *image.get_pixel(x, y) == background {
+                continue;
+            }
+            if let Some(blurred) =
+                convolve_pixel(kernel, radius, background, |offset| {
+                    let nx = x as i64 + offset;
+                    if nx < 0 || nx >= i64::from(width) {
+                        None
+                    } else {
+                        Some(*image.get_pixel(nx as u32, y))
+                    }
+                })
+            {
+                out.put_pixel(x, y, blurred);
+            }
+        }
+    }
+    out
+}
+
+fn convolve_vertical(image: &RgbaImage, kernel: &[f64], background: Rgba<u8>) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let radius = (kernel.len() / 2) as i64;
+    let mut out = image.clone();
+    for y in 0..height {
+        for x in 0..width {
+            if *image.get_pixel(x, y) == background {
+                continue;
+            }
+            if let Some(blurred) =
+                convolve_pixel(kernel, radius, background, |offset| {
+                    let ny = y as i64 + offset;
+                    if ny < 0 || ny >= i64::from(height) {
+                        None
+                    } else {
+                        Some(*image.get_pixel(x, ny as u32))
+                    }
+                })
+            {
+                out.put_pixel(x, y, blurred);
+            }
+        }
+    }
+    out
+}
+
+/// Averages the pixels `neighbor_at(offset)` returns for each `offset` in `-radius..=radius`,
+/// weighted by `kernel`, skipping out-of-bounds neighbors and ones equal to `background`.
+fn convolve_pixel(
+    kernel: &[f64],
+    radius: i64,
+    background: Rgba<u8>,
+    neighbor_at: impl Fn(i64) -> Option<Rgba<u8>>,
+) -> Option<Rgba<u8>> {
+    let mut sum = [0f64; 4];
+    let mut weight_sum = 0.;
+    for (i, &weight) in kernel.iter().enumerate() {
+        let offset = i as i64 - radius;
+        let neighbor = match neighbor_at(offset) {
+            Some(neighbor) if neighbor != background => neighbor,
+            _ => continue,
+        };
+        for c in 0..4 {
+            sum[c] += f64::from(neighbor[c]) * weight;
+        }
+        weight_sum += weight;
+    }
+    if weight_sum == 0. {
+        return None;
+    }
+    let mut blurred = [0u8; 4];
+    for (c, slot) in blurred.iter_mut().enumerate() {
+        *slot = (sum[c] / weight_sum).round() as u8;
+    }
+    Some(Rgba(blurred))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKGROUND: Color<u8> = Color {
+        red: 255,
+        green: 255,
+        blue: 255,
+        alpha: 0,
+    };
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn image_from_rows(rows: &[&[Rgba<u8>]]) -> RgbaImage {
+        let height = rows.len() as u32;
+        let width = rows[0].len() as u32;
+        let mut image = RgbaImage::new(width, height);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &pixel) in row.iter().enumerate() {
+                image.put_pixel(x as u32, y as u32, pixel);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn fill_gaps_leaves_real_pixels_untouched() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, background, RED]]);
+        fill_gaps(&mut image, BACKGROUND, 1);
+        assert_eq!(*image.get_pixel(0, 0), RED);
+        assert_eq!(*image.get_pixel(2, 0), RED);
+    }
+
+    #[test]
+    fn fill_gaps_replaces_background_within_radius_with_nearest_real_pixel() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, background, background]]);
+        fill_gaps(&mut image, BACKGROUND, 2);
+        assert_eq!(*image.get_pixel(1, 0), RED);
+        assert_eq!(*image.get_pixel(2, 0), RED);
+    }
+
+    #[test]
+    fn fill_gaps_leaves_background_untouched_outside_radius() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, background, background, background]]);
+        fill_gaps(&mut image, BACKGROUND, 1);
+        assert_eq!(*image.get_pixel(1, 0), RED);
+        assert_eq!(*image.get_pixel(3, 0), background);
+    }
+
+    #[test]
+    fn fill_gaps_is_a_no_op_for_radius_zero() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, background, RED]]);
+        fill_gaps(&mut image, BACKGROUND, 0);
+        assert_eq!(*image.get_pixel(1, 0), background);
+    }
+
+    #[test]
+    fn blur_never_lets_background_bleed_into_real_pixels_or_vice_versa() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, RED, background, background]]);
+        blur(&mut image, BACKGROUND, 1.0);
+        // The background pixels are never blurred themselves...
+        assert_eq!(*image.get_pixel(2, 0), background);
+        assert_eq!(*image.get_pixel(3, 0), background);
+        // ...and their color never gets mixed into a neighboring real pixel's result.
+        assert_eq!(*image.get_pixel(1, 0), RED);
+    }
+
+    #[test]
+    fn blur_is_a_no_op_for_sigma_zero() {
+        let background = as_rgba(BACKGROUND);
+        let mut image = image_from_rows(&[&[RED, background, RED]]);
+        blur(&mut image, BACKGROUND, 0.0);
+        assert_eq!(*image.get_pixel(1, 0), background);
+    }
+}
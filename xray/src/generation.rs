@@ -0,0 +1,867 @@
+//! Rasterizes a point cloud into a 2D "X-Ray" tile: a top-down projection of the points in a
+//! bounding box onto a grid of pixels, colored according to a `ColoringStrategy`.
+
+use crate::colormap::Colormap;
+use cgmath::{EuclideanSpace, Point2, Point3, Vector2, Vector3};
+use clap::arg_enum;
+use collision::{Aabb, Aabb2, Aabb3};
+use fnv::FnvHashMap;
+use image::{Rgba, RgbaImage};
+use point_cloud_client::PointCloudClient;
+use point_viewer::color::Color;
+use std::path::Path;
+
+arg_enum! {
+    #[derive(Debug)]
+    #[allow(non_camel_case_types)]
+    pub enum ColoringStrategyArgument {
+        xray,
+        colored,
+        colored_with_intensity,
+        colored_with_height_stddev,
+        colored_with_colormap,
+        colored_by_axis,
+        colored_by_slope,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug)]
+    #[allow(non_camel_case_types)]
+    pub enum TileBackgroundColorArgument {
+        white,
+        transparent,
+    }
+}
+
+/// The scalar a `ColoredWithColormap` strategy reads out of a point before mapping it through
+/// its colormap.
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(non_camel_case_types)]
+    pub enum ColormapSourceArgument {
+        height,
+        intensity,
+        density,
+    }
+}
+
+/// World axis a `ColoredByAxis` strategy colors by.
+arg_enum! {
+    #[derive(Debug, Clone, Copy)]
+    #[allow(non_camel_case_types)]
+    pub enum Axis {
+        x,
+        y,
+        z,
+    }
+}
+
+/// Per-point data handed to a `ColoringStrategy` as the point cloud is rasterized.
+pub struct PointAttributes<'a> {
+    pub position: &'a Point3<f64>,
+    pub color: &'a Color<u8>,
+    pub intensity: Option<f32>,
+}
+
+#[derive(Debug)]
+pub enum ColoringStrategyKind {
+    XRay,
+    Colored,
+    ColoredWithIntensity(f32, f32),
+    ColoredWithHeightStddev(f32),
+    ColoredWithColormap {
+        colormap: Colormap,
+        domain_min: f32,
+        domain_max: f32,
+        source: ColormapSourceArgument,
+    },
+    ColoredByAxis {
+        axis: Axis,
+        colormap: Colormap,
+        domain_min: f32,
+        domain_max: f32,
+    },
+    ColoredBySlope {
+        colormap: Colormap,
+        max_slope: f32,
+    },
+}
+
+/// A fixed, stable set of fields describing a `ColoringStrategyKind`, for a metadata sidecar that
+/// a downstream renderer parses. Unlike `{:?}`, this shape does not change when a variant or
+/// field is renamed: every field is always present, `null` where a strategy does not use it.
+pub struct ColoringStrategyMeta {
+    pub name: &'static str,
+    pub colormap: Option<Colormap>,
+    pub domain_min: Option<f32>,
+    pub domain_max: Option<f32>,
+    pub axis: Option<Axis>,
+    pub max_slope: Option<f32>,
+}
+
+impl ColoringStrategyMeta {
+    fn named(name: &'static str) -> Self {
+        ColoringStrategyMeta {
+            name,
+            colormap: None,
+            domain_min: None,
+            domain_max: None,
+            axis: None,
+            max_slope: None,
+        }
+    }
+}
+
+impl ColoringStrategyKind {
+    /// Describes this strategy as a `ColoringStrategyMeta`, for writing into a metadata sidecar.
+    pub fn describe(&self) -> ColoringStrategyMeta {
+        match *self {
+            ColoringStrategyKind::XRay => ColoringStrategyMeta::named("xray"),
+            ColoringStrategyKind::Colored => ColoringStrategyMeta::named("colored"),
+            ColoringStrategyKind::ColoredWithIntensity(domain_min, domain_max) => {
+                ColoringStrategyMeta {
+                    domain_min: Some(domain_min),
+                    domain_max: Some(domain_max),
+                    ..ColoringStrategyMeta::named("colored_with_intensity")
+                }
+            }
+            ColoringStrategyKind::ColoredWithHeightStddev(domain_max) => ColoringStrategyMeta {
+                domain_max: Some(domain_max),
+                ..ColoringStrategyMeta::named("colored_with_height_stddev")
+            },
+            ColoringStrategyKind::ColoredWithColormap {
+                colormap,
+                domain_min,
+                domain_max,
+                ..
+            } => ColoringStrategyMeta {
+                colormap: Some(colormap),
+                domain_min: Some(domain_min),
+                domain_max: Some(domain_max),
+                ..ColoringStrategyMeta::named("colored_with_colormap")
+            },
+            ColoringStrategyKind::ColoredByAxis {
+                axis,
+                colormap,
+                domain_min,
+                domain_max,
+            } => ColoringStrategyMeta {
+                axis: Some(axis),
+                colormap: Some(colormap),
+                domain_min: Some(domain_min),
+                domain_max: Some(domain_max),
+                ..ColoringStrategyMeta::named("colored_by_axis")
+            },
+            ColoringStrategyKind::ColoredBySlope { colormap, max_slope } => ColoringStrategyMeta {
+                colormap: Some(colormap),
+                max_slope: Some(max_slope),
+                ..ColoringStrategyMeta::named("colored_by_slope")
+            },
+        }
+    }
+
+    pub fn new_strategy(&self) -> Box<dyn ColoringStrategy> {
+        match *self {
+            ColoringStrategyKind::XRay => Box::new(XRayColoringStrategy::new()),
+            ColoringStrategyKind::Colored => Box::new(PointColorColoringStrategy::new()),
+            ColoringStrategyKind::ColoredWithIntensity(min_intensity, max_intensity) => {
+                Box::new(IntensityColoringStrategy::new(min_intensity, max_intensity))
+            }
+            ColoringStrategyKind::ColoredWithHeightStddev(max_stddev) => {
+                Box::new(HeightStddevColoringStrategy::new(max_stddev))
+            }
+            ColoringStrategyKind::ColoredWithColormap {
+                colormap,
+                domain_min,
+                domain_max,
+                source,
+            } => Box::new(ColormapColoringStrategy::new(
+                colormap,
+                domain_min,
+                domain_max,
+                source,
+            )),
+            ColoringStrategyKind::ColoredByAxis {
+                axis,
+                colormap,
+                domain_min,
+                domain_max,
+            } => Box::new(AxisColoringStrategy::new(axis, colormap, domain_min, domain_max)),
+            ColoringStrategyKind::ColoredBySlope {
+                colormap,
+                max_slope,
+            } => Box::new(SlopeColoringStrategy::new(colormap, max_slope)),
+        }
+    }
+}
+
+/// Accumulates points falling into each pixel of the output tile and turns the accumulated data
+/// into a final color once all points have been seen.
+pub trait ColoringStrategy {
+    /// Processes one point that was rasterized into the pixel at `(x, y)`.
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes);
+
+    /// Writes the finalized color of every pixel that received at least one point into `image`.
+    /// Pixels that never saw a point are left untouched, so the tile's background color shows
+    /// through where there is genuinely no data.
+    fn finalize(&self, image: &mut RgbaImage);
+}
+
+fn put_pixel(image: &mut RgbaImage, x: u32, y: u32, color: Color<u8>) {
+    image.put_pixel(x, y, Rgba([color.red, color.green, color.blue, color.alpha]));
+}
+
+pub struct XRayColoringStrategy {
+    // Number of points that landed in each pixel. More points means a darker pixel; every pixel
+    // that saw a point is fully opaque.
+    counts: FnvHashMap<(u32, u32), u32>,
+    max_saturation: f32,
+}
+
+impl XRayColoringStrategy {
+    pub fn new() -> Self {
+        XRayColoringStrategy {
+            counts: FnvHashMap::default(),
+            max_saturation: 50.,
+        }
+    }
+}
+
+impl ColoringStrategy for XRayColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, _point: &PointAttributes) {
+        *self.counts.entry((x, y)).or_insert(0) += 1;
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        for (&(x, y), &count) in &self.counts {
+            let saturation = (count as f32 / self.max_saturation).min(1.);
+            let value = (255. * (1. - saturation)) as u8;
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red: value,
+                    green: value,
+                    blue: value,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+pub struct PointColorColoringStrategy {
+    sums: FnvHashMap<(u32, u32), (Vector3<f32>, u32)>,
+}
+
+impl PointColorColoringStrategy {
+    pub fn new() -> Self {
+        PointColorColoringStrategy {
+            sums: FnvHashMap::default(),
+        }
+    }
+}
+
+impl ColoringStrategy for PointColorColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        let entry = self.sums.entry((x, y)).or_insert((Vector3::new(0., 0., 0.), 0));
+        entry.0 += Vector3::new(
+            f32::from(point.color.red),
+            f32::from(point.color.green),
+            f32::from(point.color.blue),
+        );
+        entry.1 += 1;
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        for (&(x, y), &(sum, count)) in &self.sums {
+            let average = sum / (count as f32);
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red: average.x as u8,
+                    green: average.y as u8,
+                    blue: average.z as u8,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+pub struct IntensityColoringStrategy {
+    min_intensity: f32,
+    max_intensity: f32,
+    sums: FnvHashMap<(u32, u32), (f32, u32)>,
+}
+
+impl IntensityColoringStrategy {
+    pub fn new(min_intensity: f32, max_intensity: f32) -> Self {
+        IntensityColoringStrategy {
+            min_intensity,
+            max_intensity,
+            sums: FnvHashMap::default(),
+        }
+    }
+}
+
+impl ColoringStrategy for IntensityColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        let intensity = point.intensity.unwrap_or(0.);
+        let entry = self.sums.entry((x, y)).or_insert((0., 0));
+        entry.0 += intensity;
+        entry.1 += 1;
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        let range = (self.max_intensity - self.min_intensity).max(std::f32::EPSILON);
+        for (&(x, y), &(sum, count)) in &self.sums {
+            let average = sum / (count as f32);
+            let t = ((average - self.min_intensity) / range).max(0.).min(1.);
+            let value = (t * 255.) as u8;
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red: value,
+                    green: value,
+                    blue: value,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+pub struct HeightStddevColoringStrategy {
+    max_stddev: f32,
+    heights: FnvHashMap<(u32, u32), Vec<f32>>,
+}
+
+impl HeightStddevColoringStrategy {
+    pub fn new(max_stddev: f32) -> Self {
+        HeightStddevColoringStrategy {
+            max_stddev,
+            heights: FnvHashMap::default(),
+        }
+    }
+}
+
+impl ColoringStrategy for HeightStddevColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        self.heights
+            .entry((x, y))
+            .or_insert_with(Vec::new)
+            .push(point.position.z as f32);
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        for (&(x, y), heights) in &self.heights {
+            let mean = heights.iter().sum::<f32>() / (heights.len() as f32);
+            let variance = heights.iter().map(|h| (h - mean) * (h - mean)).sum::<f32>()
+                / (heights.len() as f32);
+            let stddev = variance.sqrt();
+            let t = (stddev / self.max_stddev).min(1.);
+            let value = (t * 255.) as u8;
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red: value,
+                    green: value,
+                    blue: value,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+/// Colors each pixel by mapping a scalar - the height, the intensity, or the number of points
+/// that landed in the pixel - through a perceptual `Colormap`.
+pub struct ColormapColoringStrategy {
+    colormap: Colormap,
+    domain_min: f32,
+    domain_max: f32,
+    source: ColormapSourceArgument,
+    // Accumulated per pixel: sum of the scalar read from `source` and the number of points seen,
+    // so the final value is the average over all points that fell into the pixel, or for the
+    // `density` source the point count itself.
+    sums: FnvHashMap<(u32, u32), (f32, u32)>,
+}
+
+impl ColormapColoringStrategy {
+    pub fn new(
+        colormap: Colormap,
+        domain_min: f32,
+        domain_max: f32,
+        source: ColormapSourceArgument,
+    ) -> Self {
+        ColormapColoringStrategy {
+            colormap,
+            domain_min,
+            domain_max,
+            source,
+            sums: FnvHashMap::default(),
+        }
+    }
+
+    fn scalar_of(&self, point: &PointAttributes) -> f32 {
+        match self.source {
+            ColormapSourceArgument::height => point.position.z as f32,
+            ColormapSourceArgument::intensity => point.intensity.unwrap_or(0.),
+            ColormapSourceArgument::density => 1.,
+        }
+    }
+}
+
+impl ColoringStrategy for ColormapColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        let scalar = self.scalar_of(point);
+        let entry = self.sums.entry((x, y)).or_insert((0., 0));
+        entry.0 += scalar;
+        entry.1 += 1;
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        let range = (self.domain_max - self.domain_min).max(std::f32::EPSILON);
+        for (&(x, y), &(sum, count)) in &self.sums {
+            let value = if self.source == ColormapSourceArgument::density {
+                sum
+            } else {
+                sum / (count as f32)
+            };
+            let t = (value - self.domain_min) / range;
+            let [red, green, blue] = self.colormap.map(t);
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red,
+                    green,
+                    blue,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+/// Colors each pixel by the average position of its points along a single world axis, mapped
+/// through a perceptual `Colormap`. Useful for reading terrain elevation directly off a tile
+/// when colored by the Z axis.
+pub struct AxisColoringStrategy {
+    axis: Axis,
+    colormap: Colormap,
+    domain_min: f32,
+    domain_max: f32,
+    sums: FnvHashMap<(u32, u32), (f32, u32)>,
+}
+
+impl AxisColoringStrategy {
+    pub fn new(axis: Axis, colormap: Colormap, domain_min: f32, domain_max: f32) -> Self {
+        AxisColoringStrategy {
+            axis,
+            colormap,
+            domain_min,
+            domain_max,
+            sums: FnvHashMap::default(),
+        }
+    }
+
+    fn scalar_of(&self, point: &PointAttributes) -> f32 {
+        match self.axis {
+            Axis::x => point.position.x as f32,
+            Axis::y => point.position.y as f32,
+            Axis::z => point.position.z as f32,
+        }
+    }
+}
+
+impl ColoringStrategy for AxisColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        let scalar = self.scalar_of(point);
+        let entry = self.sums.entry((x, y)).or_insert((0., 0));
+        entry.0 += scalar;
+        entry.1 += 1;
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        let range = (self.domain_max - self.domain_min).max(std::f32::EPSILON);
+        for (&(x, y), &(sum, count)) in &self.sums {
+            let average = sum / (count as f32);
+            let t = (average - self.domain_min) / range;
+            let [red, green, blue] = self.colormap.map(t);
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red,
+                    green,
+                    blue,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+/// Colors each pixel by the local surface slope: the larger of the cross-pixel gradient between a
+/// pixel's ground height and its 3x3 neighborhood, and the within-pixel relief between its lowest
+/// and highest point, mapped through a perceptual `Colormap`. This highlights curbs, walls and
+/// embankments that are invisible in a plain density X-ray.
+pub struct SlopeColoringStrategy {
+    colormap: Colormap,
+    max_slope: f32,
+    // Per pixel, the minimum and maximum height seen. The minimum is used as the pixel's ground
+    // height for gradient estimation; the maximum combines with it into the within-pixel relief.
+    heights: FnvHashMap<(u32, u32), (f32, f32)>,
+}
+
+impl SlopeColoringStrategy {
+    pub fn new(colormap: Colormap, max_slope: f32) -> Self {
+        SlopeColoringStrategy {
+            colormap,
+            max_slope,
+            heights: FnvHashMap::default(),
+        }
+    }
+}
+
+impl ColoringStrategy for SlopeColoringStrategy {
+    fn process_point(&mut self, x: u32, y: u32, point: &PointAttributes) {
+        let z = point.position.z as f32;
+        let entry = self.heights.entry((x, y)).or_insert((z, z));
+        entry.0 = entry.0.min(z);
+        entry.1 = entry.1.max(z);
+    }
+
+    fn finalize(&self, image: &mut RgbaImage) {
+        for (&(x, y), &(ground_height, max_height)) in &self.heights {
+            let mut gradient = 0f32;
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let (neighbor_x, neighbor_y) = (x as i64 + dx, y as i64 + dy);
+                    if neighbor_x < 0 || neighbor_y < 0 {
+                        continue;
+                    }
+                    if let Some(&(neighbor_height, _)) =
+                        self.heights.get(&(neighbor_x as u32, neighbor_y as u32))
+                    {
+                        // Normalize by the neighbor's pixel distance so a diagonal neighbor
+                        // (sqrt(2) pixels away) isn't treated as steep as an axis neighbor with
+                        // the same height delta.
+                        let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                        gradient = gradient.max((neighbor_height - ground_height).abs() / distance);
+                    }
+                }
+            }
+            // A vertical feature entirely within one pixel (e.g. a wall) has no cross-pixel
+            // gradient to detect, so also factor in the within-pixel relief between the highest
+            // and lowest point seen.
+            let relief = max_height - ground_height;
+            let slope = gradient.max(relief);
+            let t = (slope / self.max_slope).min(1.);
+            let [red, green, blue] = self.colormap.map(t);
+            put_pixel(
+                image,
+                x,
+                y,
+                Color {
+                    red,
+                    green,
+                    blue,
+                    alpha: 255,
+                },
+            );
+        }
+    }
+}
+
+/// Number of buckets in the histogram `auto_range` accumulates the scalar into. Fixed regardless
+/// of point count, so a tile with a billion points costs the same handful of kilobytes as one
+/// with a thousand.
+const HISTOGRAM_BUCKETS: usize = 1024;
+
+/// A fixed-size histogram of a scalar's distribution over `[min, max]`, used to derive percentile
+/// bounds without accumulating every point's value into an unbounded `Vec` (which would make
+/// `auto_range` an O(n) allocation and O(n log n) sort over the whole point cloud).
+struct ScalarHistogram {
+    min: f32,
+    max: f32,
+    counts: [u64; HISTOGRAM_BUCKETS],
+}
+
+impl ScalarHistogram {
+    fn new(min: f32, max: f32) -> Self {
+        ScalarHistogram {
+            min,
+            max,
+            counts: [0; HISTOGRAM_BUCKETS],
+        }
+    }
+
+    fn bucket_of(&self, value: f32) -> usize {
+        if self.max <= self.min {
+            return 0;
+        }
+        let t = ((value - self.min) / (self.max - self.min)).max(0.).min(1.);
+        ((t * HISTOGRAM_BUCKETS as f32) as usize).min(HISTOGRAM_BUCKETS - 1)
+    }
+
+    fn add(&mut self, value: f32) {
+        let bucket = self.bucket_of(value);
+        self.counts[bucket] += 1;
+    }
+
+    /// Returns the value at the `clip_percentile`-th and `(100 - clip_percentile)`-th percentiles
+    /// of the accumulated distribution, rounded out to the edges of the bucket they fall into.
+    fn percentile_range(&self, clip_percentile: f64) -> (f32, f32) {
+        let total: u64 = self.counts.iter().sum();
+        if total == 0 {
+            return (self.min, self.max);
+        }
+        let low_rank = ((clip_percentile / 100.) * total as f64) as u64;
+        let high_rank = ((1. - clip_percentile / 100.) * total as f64) as u64;
+        let bucket_width = (self.max - self.min) / HISTOGRAM_BUCKETS as f32;
+        let low_bucket = self.bucket_at_rank(low_rank.min(total - 1));
+        let high_bucket = self.bucket_at_rank(high_rank.min(total - 1));
+        (
+            self.min + low_bucket as f32 * bucket_width,
+            self.min + (high_bucket + 1) as f32 * bucket_width,
+        )
+    }
+
+    /// Finds the bucket containing the `rank`-th value (0-indexed) in sorted order.
+    fn bucket_at_rank(&self, rank: u64) -> usize {
+        let mut cumulative = 0u64;
+        for (bucket, &count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative > rank {
+                return bucket;
+            }
+        }
+        HISTOGRAM_BUCKETS - 1
+    }
+}
+
+/// Performs a cheap first pass over every point in `bbox` to find the scalar's min/max that
+/// `scalar_of` reads out of each point, then a second pass to bucket it into a fixed-size
+/// histogram, and returns the `clip_percentile`-th and `(100 - clip_percentile)`-th percentiles of
+/// that histogram. Using percentiles rather than the absolute min/max keeps a handful of outlier
+/// points from washing out the whole tile; using a histogram rather than collecting every value
+/// keeps memory bounded no matter how large the point cloud is.
+pub fn auto_range(
+    point_cloud_client: &PointCloudClient,
+    frame: &Option<String>,
+    bbox: &Aabb3<f64>,
+    clip_percentile: f64,
+    scalar_of: impl Fn(&PointAttributes) -> f32,
+) -> (f32, f32) {
+    let mut min = std::f32::INFINITY;
+    let mut max = std::f32::NEG_INFINITY;
+    point_cloud_client
+        .for_each_point(frame, bbox, |position, color, intensity| {
+            let value = scalar_of(&PointAttributes {
+                position: &position,
+                color: &color,
+                intensity,
+            });
+            min = min.min(value);
+            max = max.max(value);
+        })
+        .expect("Could not iterate points in bounding box.");
+    if !min.is_finite() || !max.is_finite() {
+        return (0., 1.);
+    }
+
+    let mut histogram = ScalarHistogram::new(min, max);
+    point_cloud_client
+        .for_each_point(frame, bbox, |position, color, intensity| {
+            histogram.add(scalar_of(&PointAttributes {
+                position: &position,
+                color: &color,
+                intensity,
+            }));
+        })
+        .expect("Could not iterate points in bounding box.");
+    histogram.percentile_range(clip_percentile)
+}
+
+/// Replaces the hand-tuned bounds of `kind` with ones auto-detected from the data: the
+/// `clip_percentile`/`(100 - clip_percentile)` percentiles of the scalar the strategy colors by,
+/// computed over every point in `bbox`. Strategies that have no such bounds are returned
+/// unchanged. The detected bounds are logged so a user can pin them down for a follow-up run.
+pub fn with_auto_range(
+    point_cloud_client: &PointCloudClient,
+    frame: &Option<String>,
+    bbox: &Aabb3<f64>,
+    clip_percentile: f64,
+    kind: ColoringStrategyKind,
+) -> ColoringStrategyKind {
+    match kind {
+        ColoringStrategyKind::ColoredWithIntensity(_, _) => {
+            let (min, max) = auto_range(point_cloud_client, frame, bbox, clip_percentile, |p| {
+                p.intensity.unwrap_or(0.)
+            });
+            println!("auto_range: detected intensity range [{}, {}]", min, max);
+            ColoringStrategyKind::ColoredWithIntensity(min, max)
+        }
+        ColoringStrategyKind::ColoredWithHeightStddev(_) => {
+            let (min, max) = auto_range(point_cloud_client, frame, bbox, clip_percentile, |p| {
+                p.position.z as f32
+            });
+            // There is no single scalar per point to take a percentile of for a stddev-based
+            // strategy, so we derive a reasonable ceiling from the clipped height range instead.
+            let max_stddev = ((max - min) / 4.).max(std::f32::EPSILON);
+            println!("auto_range: detected max height stddev {}", max_stddev);
+            ColoringStrategyKind::ColoredWithHeightStddev(max_stddev)
+        }
+        ColoringStrategyKind::ColoredWithColormap {
+            colormap,
+            source,
+            domain_min,
+            domain_max,
+        } => {
+            let (min, max) = match source {
+                ColormapSourceArgument::height => {
+                    auto_range(point_cloud_client, frame, bbox, clip_percentile, |p| {
+                        p.position.z as f32
+                    })
+                }
+                ColormapSourceArgument::intensity => {
+                    auto_range(point_cloud_client, frame, bbox, clip_percentile, |p| {
+                        p.intensity.unwrap_or(0.)
+                    })
+                }
+                // Density has no per-point scalar to take a percentile of, so it keeps its
+                // user-specified (or default) domain.
+                ColormapSourceArgument::density => (domain_min, domain_max),
+            };
+            println!("auto_range: detected colormap domain [{}, {}]", min, max);
+            ColoringStrategyKind::ColoredWithColormap {
+                colormap,
+                domain_min: min,
+                domain_max: max,
+                source,
+            }
+        }
+        ColoringStrategyKind::ColoredByAxis { axis, colormap, .. } => {
+            let (min, max) =
+                auto_range(point_cloud_client, frame, bbox, clip_percentile, |p| match axis {
+                    Axis::x => p.position.x as f32,
+                    Axis::y => p.position.y as f32,
+                    Axis::z => p.position.z as f32,
+                });
+            println!("auto_range: detected axis domain [{}, {}]", min, max);
+            ColoringStrategyKind::ColoredByAxis {
+                axis,
+                colormap,
+                domain_min: min,
+                domain_max: max,
+            }
+        }
+        other => other,
+    }
+}
+
+fn pixel_coordinates(bbox2: &Aabb2<f64>, image_size: Vector2<u32>, p: &Point3<f64>) -> (u32, u32) {
+    let x = (((p.x - bbox2.min().x) / bbox2.dim().x) * f64::from(image_size.x)) as u32;
+    let y = (((p.y - bbox2.min().y) / bbox2.dim().y) * f64::from(image_size.y)) as u32;
+    (
+        x.min(image_size.x - 1),
+        image_size.y - 1 - y.min(image_size.y - 1),
+    )
+}
+
+/// Rasterizes all points from `point_cloud_client` that fall into `bbox` into a tile of
+/// `image_size` pixels, colored according to `coloring_strategy`. Returns `None` if no points
+/// were found in `bbox`.
+pub fn xray_image_from_points(
+    point_cloud_client: &PointCloudClient,
+    frame: &Option<String>,
+    bbox: &Aabb3<f64>,
+    image_size: Vector2<u32>,
+    mut coloring_strategy: Box<dyn ColoringStrategy>,
+    tile_background_color: Color<u8>,
+) -> Option<RgbaImage> {
+    let bbox2 = Aabb2::new(
+        Point2::new(bbox.min().x, bbox.min().y),
+        Point2::new(bbox.max().x, bbox.max().y),
+    );
+    let mut seen_a_point = false;
+    point_cloud_client
+        .for_each_point(frame, bbox, |position, color, intensity| {
+            seen_a_point = true;
+            let (x, y) = pixel_coordinates(&bbox2, image_size, &position);
+            coloring_strategy.process_point(
+                x,
+                y,
+                &PointAttributes {
+                    position: &position,
+                    color: &color,
+                    intensity,
+                },
+            );
+        })
+        .expect("Could not iterate points in bounding box.");
+
+    if !seen_a_point {
+        return None;
+    }
+
+    let mut image = RgbaImage::from_pixel(
+        image_size.x,
+        image_size.y,
+        Rgba([
+            tile_background_color.red,
+            tile_background_color.green,
+            tile_background_color.blue,
+            tile_background_color.alpha,
+        ]),
+    );
+    coloring_strategy.finalize(&mut image);
+    Some(image)
+}
+
+/// Rasterizes all points from `point_cloud_client` that fall into `bbox` into a tile of
+/// `image_size` pixels, colors it according to `coloring_strategy` and writes it to
+/// `output_filename`. Returns `false` without writing a file if no points were found.
+pub fn xray_from_points(
+    point_cloud_client: &PointCloudClient,
+    frame: &Option<String>,
+    bbox: &Aabb3<f64>,
+    output_filename: &Path,
+    image_size: Vector2<u32>,
+    coloring_strategy: Box<dyn ColoringStrategy>,
+    tile_background_color: Color<u8>,
+) -> bool {
+    let image = xray_image_from_points(
+        point_cloud_client,
+        frame,
+        bbox,
+        image_size,
+        coloring_strategy,
+        tile_background_color,
+    );
+    match image {
+        Some(image) => {
+            image
+                .save(output_filename)
+                .expect("Could not write output PNG file.");
+            true
+        }
+        None => false,
+    }
+}
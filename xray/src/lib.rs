@@ -0,0 +1,4 @@
+pub mod colormap;
+pub mod filters;
+pub mod generation;
+pub mod pyramid;
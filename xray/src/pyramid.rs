@@ -0,0 +1,324 @@
+//! Generates a pyramid of X-Ray tiles covering a bounding box at multiple zoom levels, so that
+//! covering a large area at web-map zoom levels does not require orchestrating thousands of
+//! manual `build_xray_tile` invocations.
+//!
+//! The finest level is rasterized directly from the point cloud, one leaf tile at a time, via
+//! `generation::xray_image_from_points`. Every coarser level is built by 2x2 downsampling of the
+//! four already-rendered child tiles, so the point cloud is only ever queried at the finest
+//! resolution. Tiles are written into `output_directory` as `z/x/y.png`, following the same
+//! `z/x/y` layout as other slippy-map tile pyramids, with `y` counted from the south-west corner
+//! of `bbox` (i.e. TMS, not the north-origin XYZ convention).
+
+use crate::filters;
+use crate::generation::{xray_image_from_points, ColoringStrategyKind, ColoringStrategyMeta};
+use cgmath::{Point2, Point3, Vector2};
+use collision::{Aabb, Aabb2, Aabb3};
+use image::{imageops, Rgba, RgbaImage};
+use point_cloud_client::PointCloudClient;
+use point_viewer::color::Color;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Size in pixels of one side of a (square) pyramid tile.
+pub const TILE_SIZE: u32 = 256;
+
+/// Magic string at the start of the metadata sidecar, identifying the file format to a
+/// downstream slippy-map viewer.
+const META_MAGIC: &str = "XRAYPYR1";
+
+/// Version of the metadata sidecar format emitted by `build_pyramid`.
+const META_VERSION: u32 = 1;
+
+/// Describes a tile pyramid written by `build_pyramid`, so a downstream viewer can discover its
+/// levels, tile size and coloring without parsing every tile. Mirrors the header of a typical
+/// tile-map format: a magic string, a version, and the geometry of the pyramid.
+pub struct PyramidMeta {
+    pub num_levels: u32,
+    pub tile_size: u32,
+    pub coloring_strategy: ColoringStrategyMeta,
+    pub resolution: f64,
+    pub bbox: Aabb3<f64>,
+}
+
+/// Renders `value` as a JSON number, or `null` if absent.
+fn json_num(value: Option<f32>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "null".to_string(),
+    }
+}
+
+/// Renders `value` as a quoted JSON string, or `null` if absent.
+fn json_str<T: fmt::Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => format!("\"{}\"", value),
+        None => "null".to_string(),
+    }
+}
+
+impl PyramidMeta {
+    /// Writes this metadata as a small JSON sidecar to `path`. The set of fields is fixed and
+    /// does not depend on which coloring strategy was used: a field a strategy does not need is
+    /// `null` rather than omitted, so a downstream parser can rely on a stable schema.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let strategy = &self.coloring_strategy;
+        let json = format!(
+            "{{\n  \"magic\": \"{}\",\n  \"version\": {},\n  \"tile_size\": {},\n  \
+             \"num_levels\": {},\n  \"resolution\": {},\n  \
+             \"coloring_strategy\": {{\n    \"name\": \"{}\",\n    \"colormap\": {},\n    \
+             \"domain_min\": {},\n    \"domain_max\": {},\n    \"axis\": {},\n    \
+             \"max_slope\": {}\n  }},\n  \
+             \"bbox\": {{ \"min\": [{}, {}, {}], \"max\": [{}, {}, {}] }}\n}}\n",
+            META_MAGIC,
+            META_VERSION,
+            self.tile_size,
+            self.num_levels,
+            self.resolution,
+            strategy.name,
+            json_str(strategy.colormap),
+            json_num(strategy.domain_min),
+            json_num(strategy.domain_max),
+            json_str(strategy.axis),
+            json_num(strategy.max_slope),
+            self.bbox.min().x,
+            self.bbox.min().y,
+            self.bbox.min().z,
+            self.bbox.max().x,
+            self.bbox.max().y,
+            self.bbox.max().z,
+        );
+        fs::write(path, json)
+    }
+}
+
+/// Generates a quadtree pyramid of `num_levels` zoom levels covering `bbox` and writes it into
+/// `output_directory` as `z/x/y.png` tiles of `TILE_SIZE` pixels, plus a `meta.json` sidecar
+/// describing the pyramid. `fill_radius` and `blur_sigma` are applied to each rasterized leaf
+/// tile before it is written, the same as in `build_xray_tile`; coarser levels inherit the
+/// filtered result through downsampling rather than being filtered again themselves.
+pub fn build_pyramid(
+    point_cloud_client: &PointCloudClient,
+    bbox: &Aabb3<f64>,
+    num_levels: u32,
+    coloring_strategy_kind: &ColoringStrategyKind,
+    tile_background_color: Color<u8>,
+    output_directory: &Path,
+    fill_radius: u32,
+    blur_sigma: f64,
+) -> io::Result<()> {
+    assert!(num_levels > 0, "num_levels must be positive.");
+    let finest_level = num_levels - 1;
+    let tiles_per_side = 1u32 << finest_level;
+    let bbox2 = Aabb2::new(
+        Point2::new(bbox.min().x, bbox.min().y),
+        Point2::new(bbox.max().x, bbox.max().y),
+    );
+    let tile_world_size = Vector2::new(
+        bbox2.dim().x / f64::from(tiles_per_side),
+        bbox2.dim().y / f64::from(tiles_per_side),
+    );
+
+    for tile_y in 0..tiles_per_side {
+        for tile_x in 0..tiles_per_side {
+            let tile_bbox2 = leaf_tile_bbox2(&bbox2, tile_world_size, tile_x, tile_y);
+            let tile_bbox3 = Aabb3::new(
+                Point3::new(tile_bbox2.min().x, tile_bbox2.min().y, bbox.min().z),
+                Point3::new(tile_bbox2.max().x, tile_bbox2.max().y, bbox.max().z),
+            );
+            let mut image = xray_image_from_points(
+                point_cloud_client,
+                &None,
+                &tile_bbox3,
+                Vector2::new(TILE_SIZE, TILE_SIZE),
+                coloring_strategy_kind.new_strategy(),
+                tile_background_color,
+            )
+            .unwrap_or_else(|| blank_tile(tile_background_color));
+            filters::fill_gaps(&mut image, tile_background_color, fill_radius);
+            filters::blur(&mut image, tile_background_color, blur_sigma);
+            write_tile(output_directory, finest_level, tile_x, tile_y, &image)?;
+        }
+    }
+
+    for level in (0..finest_level).rev() {
+        let tiles_per_side = 1u32 << level;
+        for tile_y in 0..tiles_per_side {
+            for tile_x in 0..tiles_per_side {
+                // Larger tile_y is farther north (TMS numbers y from the south-west), and
+                // `pixel_coordinates` puts larger world y at row 0 of a leaf tile, so the
+                // north children (tile_y * 2 + 1) must land in the top half of the canvas and
+                // the south children (tile_y * 2) in the bottom half; see `downsample_quad`.
+                let children = [
+                    read_tile(
+                        output_directory,
+                        level + 1,
+                        tile_x * 2,
+                        tile_y * 2 + 1,
+                        tile_background_color,
+                    )?,
+                    read_tile(
+                        output_directory,
+                        level + 1,
+                        tile_x * 2 + 1,
+                        tile_y * 2 + 1,
+                        tile_background_color,
+                    )?,
+                    read_tile(
+                        output_directory,
+                        level + 1,
+                        tile_x * 2,
+                        tile_y * 2,
+                        tile_background_color,
+                    )?,
+                    read_tile(
+                        output_directory,
+                        level + 1,
+                        tile_x * 2 + 1,
+                        tile_y * 2,
+                        tile_background_color,
+                    )?,
+                ];
+                let downsampled = downsample_quad(&children);
+                write_tile(output_directory, level, tile_x, tile_y, &downsampled)?;
+            }
+        }
+    }
+
+    let meta = PyramidMeta {
+        num_levels,
+        tile_size: TILE_SIZE,
+        coloring_strategy: coloring_strategy_kind.describe(),
+        resolution: tile_world_size.x / f64::from(TILE_SIZE),
+        bbox: Aabb3::new(
+            Point3::new(bbox.min().x, bbox.min().y, bbox.min().z),
+            Point3::new(bbox.max().x, bbox.max().y, bbox.max().z),
+        ),
+    };
+    meta.write(&output_directory.join("meta.json"))
+}
+
+fn leaf_tile_bbox2(
+    bbox2: &Aabb2<f64>,
+    tile_world_size: Vector2<f64>,
+    tile_x: u32,
+    tile_y: u32,
+) -> Aabb2<f64> {
+    let min = Point2::new(
+        bbox2.min().x + f64::from(tile_x) * tile_world_size.x,
+        bbox2.min().y + f64::from(tile_y) * tile_world_size.y,
+    );
+    let max = Point2::new(min.x + tile_world_size.x, min.y + tile_world_size.y);
+    Aabb2::new(min, max)
+}
+
+fn tile_path(output_directory: &Path, level: u32, x: u32, y: u32) -> PathBuf {
+    output_directory
+        .join(level.to_string())
+        .join(x.to_string())
+        .join(format!("{}.png", y))
+}
+
+fn write_tile(
+    output_directory: &Path,
+    level: u32,
+    x: u32,
+    y: u32,
+    image: &RgbaImage,
+) -> io::Result<()> {
+    let path = tile_path(output_directory, level, x, y);
+    fs::create_dir_all(path.parent().unwrap())?;
+    image
+        .save(&path)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))
+}
+
+/// Reads back a tile written earlier by `write_tile`, treating a tile that is missing or cannot
+/// be decoded as a blank tile of the background color.
+fn read_tile(
+    output_directory: &Path,
+    level: u32,
+    x: u32,
+    y: u32,
+    background: Color<u8>,
+) -> io::Result<RgbaImage> {
+    let path = tile_path(output_directory, level, x, y);
+    match image::open(&path) {
+        Ok(image) => Ok(image.to_rgba()),
+        Err(_) => Ok(blank_tile(background)),
+    }
+}
+
+fn blank_tile(background: Color<u8>) -> RgbaImage {
+    RgbaImage::from_pixel(
+        TILE_SIZE,
+        TILE_SIZE,
+        Rgba([
+            background.red,
+            background.green,
+            background.blue,
+            background.alpha,
+        ]),
+    )
+}
+
+/// Lays the four child tiles (north-west, north-east, south-west, south-east) out into a
+/// `2 * TILE_SIZE` square and downsamples it back down to `TILE_SIZE`, producing the parent tile.
+/// North children go in the top half of the canvas to match the north-up row order that
+/// `pixel_coordinates` uses for every rendered leaf tile.
+fn downsample_quad(children: &[RgbaImage; 4]) -> RgbaImage {
+    let mut canvas = RgbaImage::new(TILE_SIZE * 2, TILE_SIZE * 2);
+    let offsets = [
+        (0, 0),
+        (TILE_SIZE, 0),
+        (0, TILE_SIZE),
+        (TILE_SIZE, TILE_SIZE),
+    ];
+    for (child, &(offset_x, offset_y)) in children.iter().zip(offsets.iter()) {
+        for y in 0..TILE_SIZE {
+            for x in 0..TILE_SIZE {
+                canvas.put_pixel(offset_x + x, offset_y + y, *child.get_pixel(x, y));
+            }
+        }
+    }
+    imageops::resize(&canvas, TILE_SIZE, TILE_SIZE, imageops::FilterType::Triangle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_tile(color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(TILE_SIZE, TILE_SIZE, Rgba(color))
+    }
+
+    /// Downsampling four solid-colored children, tagged by which world quadrant they represent,
+    /// must place each child's color in the matching quadrant of the parent tile: north children
+    /// on top (row 0), south children on the bottom, regardless of how `build_pyramid` orders its
+    /// `read_tile` calls.
+    #[test]
+    fn downsample_quad_keeps_quadrants_aligned_with_the_world() {
+        let north_west = [255, 0, 0, 255];
+        let north_east = [0, 255, 0, 255];
+        let south_west = [0, 0, 255, 255];
+        let south_east = [255, 255, 0, 255];
+        let children = [
+            solid_tile(north_west),
+            solid_tile(north_east),
+            solid_tile(south_west),
+            solid_tile(south_east),
+        ];
+        let parent = downsample_quad(&children);
+
+        let quarter = TILE_SIZE / 4;
+        let three_quarters = TILE_SIZE - quarter;
+        assert_eq!(*parent.get_pixel(quarter, quarter), Rgba(north_west));
+        assert_eq!(*parent.get_pixel(three_quarters, quarter), Rgba(north_east));
+        assert_eq!(*parent.get_pixel(quarter, three_quarters), Rgba(south_west));
+        assert_eq!(
+            *parent.get_pixel(three_quarters, three_quarters),
+            Rgba(south_east)
+        );
+    }
+}